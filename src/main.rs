@@ -1,9 +1,13 @@
 use std::fs::{create_dir_all, File};
-use std::io::{BufRead, BufReader, Write};
-use std::path::Path;
-use std::sync::mpsc;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
 use std::thread;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const RECONNECT_MARKER: &str = "__RECONNECT__";
 
 fn timestamp() -> u64 {
     SystemTime::now()
@@ -12,20 +16,229 @@ fn timestamp() -> u64 {
         .as_secs()
 }
 
+#[derive(Clone, Copy)]
+enum TimeResolution {
+    Secs,
+    Millis,
+    Micros,
+}
+
+impl TimeResolution {
+    fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "s" => Ok(TimeResolution::Secs),
+            "ms" => Ok(TimeResolution::Millis),
+            "us" => Ok(TimeResolution::Micros),
+            other => Err(format!("Invalid time resolution: {}", other)),
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            TimeResolution::Secs => "s",
+            TimeResolution::Millis => "ms",
+            TimeResolution::Micros => "us",
+        }
+    }
+
+    fn header(&self) -> String {
+        format!("unix_{lbl},elapsed_{lbl}", lbl = self.label())
+    }
+
+    fn unix_now(&self) -> u128 {
+        let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        match self {
+            TimeResolution::Secs => since_epoch.as_secs() as u128,
+            TimeResolution::Millis => since_epoch.as_millis(),
+            TimeResolution::Micros => since_epoch.as_micros(),
+        }
+    }
+
+    fn elapsed_since(&self, start: Instant) -> u128 {
+        let elapsed = start.elapsed();
+        match self {
+            TimeResolution::Secs => elapsed.as_secs() as u128,
+            TimeResolution::Millis => elapsed.as_millis(),
+            TimeResolution::Micros => elapsed.as_micros(),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Framing {
+    Line,
+    LengthPrefixed,
+    Fixed(usize),
+}
+
+impl Framing {
+    fn parse(s: &str, default_frame_size: usize) -> Self {
+        let mut parts = s.splitn(2, ':');
+        match parts.next().unwrap_or("line") {
+            "length-prefixed" => Framing::LengthPrefixed,
+            "fixed" => {
+                let size = parts
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .unwrap_or(default_frame_size);
+                Framing::Fixed(size)
+            }
+            _ => Framing::Line,
+        }
+    }
+}
+
+fn hex_encode(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Frame length cap for `Framing::LengthPrefixed`. Guards against an out-of-sync or
+/// corrupt length prefix turning into a multi-gigabyte allocation.
+const MAX_FRAME_LEN: usize = 1 << 20;
+
+/// Assembles records out of a raw byte stream according to a `Framing` mode.
+///
+/// Bytes read off the wire are accumulated in `pending` and only turned into a record
+/// once a full line/frame is available, so a read that times out mid-frame (as serial
+/// ports do, per their configured timeout) loses nothing: the next call picks up where
+/// the last one left off instead of starting from an empty buffer.
+struct FrameReader<R> {
+    reader: R,
+    framing: Framing,
+    pending: Vec<u8>,
+    frame_len: Option<usize>,
+}
+
+impl<R: Read> FrameReader<R> {
+    fn new(reader: R, framing: Framing) -> Self {
+        Self {
+            reader,
+            framing,
+            pending: Vec::new(),
+            frame_len: None,
+        }
+    }
+
+    /// Returns the next complete record, or `Ok(None)` on a clean EOF. Framed
+    /// (non-line) records come back hex-encoded so they stay CSV-safe.
+    fn next_record(&mut self) -> std::io::Result<Option<String>> {
+        loop {
+            if let Some(record) = self.take_pending_record()? {
+                return Ok(Some(record));
+            }
+
+            let mut scratch = [0u8; 4096];
+            match self.reader.read(&mut scratch) {
+                Ok(0) => return self.take_final_record(),
+                Ok(n) => self.pending.extend_from_slice(&scratch[..n]),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Pulls a record out of `pending` if one is already fully buffered.
+    fn take_pending_record(&mut self) -> std::io::Result<Option<String>> {
+        match self.framing {
+            Framing::Line => {
+                if let Some(pos) = self.pending.iter().position(|&b| b == b'\n') {
+                    let mut line: Vec<u8> = self.pending.drain(..=pos).collect();
+                    line.pop(); // trailing '\n'
+                    if line.last() == Some(&b'\r') {
+                        line.pop();
+                    }
+                    return Self::line_from_bytes(line).map(Some);
+                }
+                Ok(None)
+            }
+            Framing::LengthPrefixed => {
+                if self.frame_len.is_none() && self.pending.len() >= 4 {
+                    let len_bytes: [u8; 4] = self.pending[..4].try_into().unwrap();
+                    let len = u32::from_be_bytes(len_bytes) as usize;
+                    if len > MAX_FRAME_LEN {
+                        self.pending.clear();
+                        return Err(std::io::Error::new(
+                            std::io::ErrorKind::InvalidData,
+                            format!("length-prefixed frame of {} bytes exceeds the {} byte cap", len, MAX_FRAME_LEN),
+                        ));
+                    }
+                    self.pending.drain(..4);
+                    self.frame_len = Some(len);
+                }
+
+                match self.frame_len {
+                    Some(len) if self.pending.len() >= len => {
+                        let frame: Vec<u8> = self.pending.drain(..len).collect();
+                        self.frame_len = None;
+                        Ok(Some(hex_encode(&frame)))
+                    }
+                    _ => Ok(None),
+                }
+            }
+            Framing::Fixed(size) => {
+                if self.pending.len() >= size {
+                    let frame: Vec<u8> = self.pending.drain(..size).collect();
+                    return Ok(Some(hex_encode(&frame)));
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    /// Called once the stream has hit a clean EOF. A length-prefixed or fixed-size
+    /// frame left incomplete at EOF is truncated and meaningless, so it's dropped. A
+    /// line left in `pending` with no trailing `\n` is still a real record, though
+    /// (e.g. the source closed the connection right after writing it), so flush it.
+    fn take_final_record(&mut self) -> std::io::Result<Option<String>> {
+        if matches!(self.framing, Framing::Line) && !self.pending.is_empty() {
+            let mut line = std::mem::take(&mut self.pending);
+            if line.last() == Some(&b'\r') {
+                line.pop();
+            }
+            return Self::line_from_bytes(line).map(Some);
+        }
+        Ok(None)
+    }
+
+    /// Validates a line's bytes as UTF-8, matching `BufRead::read_line`'s behavior of
+    /// rejecting invalid sequences instead of silently replacing them.
+    fn line_from_bytes(bytes: Vec<u8>) -> std::io::Result<String> {
+        String::from_utf8(bytes).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+}
+
 struct Args {
     directory: String,
     default_baud: u32,
     ports: Vec<String>,
+    retry_interval: u64,
+    max_retries: Option<u32>,
+    listen: Option<String>,
+    tcp: Vec<String>,
+    time_resolution: TimeResolution,
+    default_framing: Framing,
+    default_frame_size: usize,
+    rotate_secs: Option<u64>,
+    rotate_bytes: Option<u64>,
+    tmp_dir: Option<String>,
 }
 
-fn parse_port_arg(arg: &str, default_baud: u32) -> (String, u32) {
-    let mut parts = arg.splitn(2, ',');
+fn parse_port_arg(
+    arg: &str,
+    default_baud: u32,
+    default_framing: Framing,
+    default_frame_size: usize,
+) -> (String, u32, Framing) {
+    let mut parts = arg.splitn(3, ',');
     let port = parts.next().unwrap().to_string();
     let baud = parts
         .next()
         .map(|b| b.parse().unwrap_or(default_baud))
         .unwrap_or(default_baud);
-    (port, baud)
+    let framing = parts
+        .next()
+        .map(|f| Framing::parse(f, default_frame_size))
+        .unwrap_or(default_framing);
+    (port, baud, framing)
 }
 
 fn print_usage() {
@@ -34,8 +247,18 @@ fn print_usage() {
     eprintln!("OPTIONS:");
     eprintln!("  -d, --directory <DIR>        Output directory for the CSV file (required)");
     eprintln!("  -b, --default-baud <BAUD>   Default baud rate (default: 19200)");
-    eprintln!("  -p, --port <PORT[,BAUD]>    Serial port to read from. Can be specified multiple times");
-    eprintln!("                               Format: /dev/ttyUSB0 or /dev/ttyUSB0,9600");
+    eprintln!("  -p, --port <PORT[,BAUD[,FRAMING]]>  Serial port to read from. Can be specified multiple times");
+    eprintln!("                               Format: /dev/ttyUSB0 or /dev/ttyUSB0,9600 or /dev/ttyUSB0,9600,fixed:8");
+    eprintln!("  --framing {{line,length-prefixed,fixed}}  Default framing mode for serial ports (default: line)");
+    eprintln!("  --frame-size <N>             Frame size in bytes for fixed framing (default: 8)");
+    eprintln!("  --retry-interval <SECS>      Seconds to wait between reconnect attempts (default: 5)");
+    eprintln!("  --max-retries <N>            Give up on a port after N failed reconnects (default: infinite)");
+    eprintln!("  --listen <ADDR>              Also stream every record to TCP clients connecting to ADDR");
+    eprintln!("  --tcp <HOST:PORT>            TCP source to read lines from. Can be specified multiple times");
+    eprintln!("  --time-resolution {{s,ms,us}} Resolution of the recorded timestamps (default: ms)");
+    eprintln!("  --rotate-secs <SECS>         Start a new CSV file after SECS have elapsed");
+    eprintln!("  --rotate-bytes <N>           Start a new CSV file once the current one reaches N bytes");
+    eprintln!("  --tmp-dir <DIR>              Stage files here and move them into --directory once complete");
     eprintln!("  -h, --help                   Print this help message");
 }
 
@@ -45,6 +268,16 @@ fn parse_args() -> Result<Args, String> {
     let mut directory = String::new();
     let mut default_baud = 19200u32;
     let mut ports = Vec::new();
+    let mut retry_interval = 5u64;
+    let mut max_retries: Option<u32> = None;
+    let mut listen: Option<String> = None;
+    let mut tcp = Vec::new();
+    let mut time_resolution = TimeResolution::Millis;
+    let mut default_framing = Framing::Line;
+    let mut default_frame_size = 8usize;
+    let mut rotate_secs: Option<u64> = None;
+    let mut rotate_bytes: Option<u64> = None;
+    let mut tmp_dir: Option<String> = None;
 
     let mut i = 1;
     while i < args.len() {
@@ -75,6 +308,100 @@ fn parse_args() -> Result<Args, String> {
                 }
                 ports.push(args[i].clone());
             }
+            "--retry-interval" => {
+                i += 1;
+                if i >= args.len() {
+                    return Err("--retry-interval requires an argument".to_string());
+                }
+                retry_interval = args[i]
+                    .parse()
+                    .map_err(|_| format!("Invalid retry interval: {}", args[i]))?;
+            }
+            "--max-retries" => {
+                i += 1;
+                if i >= args.len() {
+                    return Err("--max-retries requires an argument".to_string());
+                }
+                max_retries = Some(
+                    args[i]
+                        .parse()
+                        .map_err(|_| format!("Invalid max retries: {}", args[i]))?,
+                );
+            }
+            "--listen" => {
+                i += 1;
+                if i >= args.len() {
+                    return Err("--listen requires an argument".to_string());
+                }
+                listen = Some(args[i].clone());
+            }
+            "--tcp" => {
+                i += 1;
+                if i >= args.len() {
+                    return Err("--tcp requires an argument".to_string());
+                }
+                tcp.push(args[i].clone());
+            }
+            "--time-resolution" => {
+                i += 1;
+                if i >= args.len() {
+                    return Err("--time-resolution requires an argument".to_string());
+                }
+                time_resolution = TimeResolution::parse(&args[i])?;
+            }
+            "--framing" => {
+                i += 1;
+                if i >= args.len() {
+                    return Err("--framing requires an argument".to_string());
+                }
+                default_framing = match args[i].as_str() {
+                    "line" => Framing::Line,
+                    "length-prefixed" => Framing::LengthPrefixed,
+                    "fixed" => Framing::Fixed(default_frame_size),
+                    other => return Err(format!("Invalid framing: {}", other)),
+                };
+            }
+            "--frame-size" => {
+                i += 1;
+                if i >= args.len() {
+                    return Err("--frame-size requires an argument".to_string());
+                }
+                default_frame_size = args[i]
+                    .parse()
+                    .map_err(|_| format!("Invalid frame size: {}", args[i]))?;
+                if let Framing::Fixed(_) = default_framing {
+                    default_framing = Framing::Fixed(default_frame_size);
+                }
+            }
+            "--rotate-secs" => {
+                i += 1;
+                if i >= args.len() {
+                    return Err("--rotate-secs requires an argument".to_string());
+                }
+                rotate_secs = Some(
+                    args[i]
+                        .parse()
+                        .map_err(|_| format!("Invalid rotate interval: {}", args[i]))?,
+                );
+            }
+            "--rotate-bytes" => {
+                i += 1;
+                if i >= args.len() {
+                    return Err("--rotate-bytes requires an argument".to_string());
+                }
+                rotate_bytes = Some(
+                    args[i]
+                        .parse()
+                        .map_err(|_| format!("Invalid rotate size: {}", args[i]))?,
+                );
+            }
+            "--tmp-dir" => {
+                i += 1;
+                if i >= args.len() {
+                    return Err("--tmp-dir requires an argument".to_string());
+                }
+                tmp_dir = Some(args[i].clone());
+            }
             arg => {
                 return Err(format!("Unknown argument: {}", arg));
             }
@@ -86,8 +413,8 @@ fn parse_args() -> Result<Args, String> {
         return Err("--directory is required".to_string());
     }
 
-    if ports.is_empty() {
-        return Err("At least one --port argument is required".to_string());
+    if ports.is_empty() && tcp.is_empty() {
+        return Err("At least one --port or --tcp argument is required".to_string());
     }
 
     if ports.len() > 8 {
@@ -98,9 +425,141 @@ fn parse_args() -> Result<Args, String> {
         directory,
         default_baud,
         ports,
+        retry_interval,
+        max_retries,
+        listen,
+        tcp,
+        time_resolution,
+        default_framing,
+        default_frame_size,
+        rotate_secs,
+        rotate_bytes,
+        tmp_dir,
     })
 }
 
+type Subscribers = Arc<Mutex<Vec<TcpStream>>>;
+
+/// Accepts subscriber connections on `addr` and registers each one in `subscribers`
+/// so the main drain loop can broadcast records to it.
+fn spawn_subscriber_listener(addr: String, subscribers: Subscribers) -> std::io::Result<()> {
+    let listener = TcpListener::bind(&addr)?;
+    println!("Listening for subscribers on {}", addr);
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    let peer = stream
+                        .peer_addr()
+                        .map(|a| a.to_string())
+                        .unwrap_or_else(|_| "unknown".to_string());
+                    // A stalled subscriber must never be able to block the writer thread,
+                    // which is also the thread writing every source's CSV record.
+                    let _ = stream.set_write_timeout(Some(Duration::from_millis(200)));
+                    eprintln!("Subscriber connected: {}", peer);
+                    subscribers.lock().unwrap().push(stream);
+                }
+                Err(e) => eprintln!("Failed to accept subscriber connection: {}", e),
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Forwards `line` to every connected subscriber, dropping any whose write fails.
+fn broadcast(subscribers: &Subscribers, line: &str) {
+    let mut subscribers = subscribers.lock().unwrap();
+    subscribers.retain_mut(|stream| stream.write_all(line.as_bytes()).is_ok());
+}
+
+/// Sleeps for `retry_interval` and bumps `attempt`, returning `false` once
+/// `max_retries` has been exhausted (an unset cap retries forever).
+fn retry(attempt: &mut u32, max_retries: Option<u32>, retry_interval: Duration, port_name: &str) -> bool {
+    *attempt += 1;
+    if max_retries.is_some_and(|max| *attempt > max) {
+        eprintln!(
+            "Giving up on {} after {} retries",
+            port_name,
+            max_retries.unwrap()
+        );
+        return false;
+    }
+    thread::sleep(retry_interval);
+    true
+}
+
+/// A single CSV file being recorded to, staged under `staging_path` and moved to
+/// `final_path` once `finalize` closes it. When the two paths differ (`--tmp-dir`
+/// given) and the rename succeeds, downstream consumers never see a
+/// partially-written file; without `--tmp-dir` both paths are the same and the file
+/// is written in place, and a cross-device move falls back to a non-atomic copy.
+struct RecordingFile {
+    file: File,
+    staging_path: PathBuf,
+    final_path: PathBuf,
+    bytes_written: u64,
+    opened_at: Instant,
+}
+
+/// Monotonic counter appended to rotated file names so two rotations within the same
+/// wall-clock second never collide (a collision would make `finalize`'s rename silently
+/// overwrite the earlier file).
+static NEXT_FILE_SEQ: AtomicU64 = AtomicU64::new(0);
+
+impl RecordingFile {
+    fn open(final_dir: &Path, staging_dir: &Path, time_resolution: TimeResolution) -> std::io::Result<Self> {
+        let seq = NEXT_FILE_SEQ.fetch_add(1, Ordering::Relaxed);
+        let name = format!("{}-{:04}.csv", timestamp(), seq);
+        let staging_path = staging_dir.join(&name);
+        let final_path = final_dir.join(&name);
+
+        let mut file = File::create(&staging_path)?;
+        let header = format!("{},port,data\n", time_resolution.header());
+        file.write_all(header.as_bytes())?;
+
+        Ok(Self {
+            file,
+            staging_path,
+            final_path,
+            bytes_written: header.len() as u64,
+            opened_at: Instant::now(),
+        })
+    }
+
+    fn write_record(&mut self, record: &str) -> std::io::Result<()> {
+        self.file.write_all(record.as_bytes())?;
+        self.bytes_written += record.len() as u64;
+        Ok(())
+    }
+
+    fn should_rotate(&self, rotate_secs: Option<u64>, rotate_bytes: Option<u64>) -> bool {
+        rotate_secs.is_some_and(|secs| self.opened_at.elapsed() >= Duration::from_secs(secs))
+            || rotate_bytes.is_some_and(|max_bytes| self.bytes_written >= max_bytes)
+    }
+
+    fn finalize(self) -> std::io::Result<PathBuf> {
+        drop(self.file);
+        if self.staging_path == self.final_path {
+            return Ok(self.final_path);
+        }
+
+        match std::fs::rename(&self.staging_path, &self.final_path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => {
+                // --tmp-dir lives on a different filesystem than --directory: rename
+                // can't do this atomically, so fall back to copy-then-remove.
+                std::fs::copy(&self.staging_path, &self.final_path)?;
+                std::fs::remove_file(&self.staging_path)?;
+            }
+            Err(e) => return Err(e),
+        }
+
+        Ok(self.final_path)
+    }
+}
+
 fn main() -> std::io::Result<()> {
     let args = match parse_args() {
         Ok(a) => a,
@@ -115,37 +574,135 @@ fn main() -> std::io::Result<()> {
     let directory = Path::new(&args.directory);
     create_dir_all(directory)?;
 
-    let file_path = directory.join(format!("{}.csv", timestamp()));
-    println!("Recording to {:?} (Ctrl+C to stop)", file_path);
+    let staging_dir = match &args.tmp_dir {
+        Some(dir) => {
+            create_dir_all(dir)?;
+            PathBuf::from(dir)
+        }
+        None => directory.to_path_buf(),
+    };
+
+    let mut current_file = RecordingFile::open(directory, &staging_dir, args.time_resolution)?;
+    println!("Recording to {:?} (Ctrl+C to stop)", current_file.staging_path);
+
+    let start = Instant::now();
 
-    let mut file = File::create(file_path)?;
+    let subscribers: Subscribers = Arc::new(Mutex::new(Vec::new()));
+    if let Some(addr) = args.listen.clone() {
+        spawn_subscriber_listener(addr, Arc::clone(&subscribers))?;
+    }
 
     let (tx, rx) = mpsc::channel::<(String, String)>();
 
-    let port_settings: Vec<(String, u32)> = args
+    let port_settings: Vec<(String, u32, Framing)> = args
         .ports
         .iter()
-        .map(|p| parse_port_arg(p, args.default_baud))
+        .map(|p| parse_port_arg(p, args.default_baud, args.default_framing, args.default_frame_size))
         .collect();
 
-    for (port_name, baud) in port_settings {
+    for (port_name, baud, framing) in port_settings {
         let tx = tx.clone();
         let port_name = port_name.clone();
+        let retry_interval = Duration::from_secs(args.retry_interval);
+        let max_retries = args.max_retries;
 
         thread::spawn(move || {
-            let port = match serialport::new(&port_name, baud).open() {
-                Ok(p) => p,
-                Err(e) => {
-                    eprintln!("Failed to open serial port {}: {}", port_name, e);
+            let mut attempt: u32 = 0;
+
+            loop {
+                let port = match serialport::new(&port_name, baud)
+                    .timeout(Duration::from_secs(1))
+                    .open()
+                {
+                    Ok(p) => p,
+                    Err(e) => {
+                        eprintln!(
+                            "Failed to open serial port {} (attempt {}): {}",
+                            port_name, attempt + 1, e
+                        );
+                        if !retry(&mut attempt, max_retries, retry_interval, &port_name) {
+                            return;
+                        }
+                        continue;
+                    }
+                };
+
+                let mut frames = FrameReader::new(port, framing);
+                attempt = 0;
+
+                loop {
+                    match frames.next_record() {
+                        Ok(Some(data)) => {
+                            let _ = tx.send((port_name.clone(), data));
+                        }
+                        Ok(None) => break,
+                        Err(ref e) if e.kind() == std::io::ErrorKind::TimedOut => continue,
+                        Err(_) => break,
+                    }
+                }
+
+                eprintln!("Serial port {} disconnected, will attempt to reconnect", port_name);
+                let _ = tx.send((port_name.clone(), RECONNECT_MARKER.to_string()));
+                if !retry(&mut attempt, max_retries, retry_interval, &port_name) {
                     return;
                 }
-            };
+            }
+        });
+    }
+
+    for addr in args.tcp.clone() {
+        let tx = tx.clone();
+        let retry_interval = Duration::from_secs(args.retry_interval);
+        let max_retries = args.max_retries;
+        let label = format!("tcp:{}", addr);
+
+        thread::spawn(move || {
+            let mut attempt: u32 = 0;
 
-            let reader = BufReader::new(port);
+            loop {
+                let stream = match TcpStream::connect(&addr) {
+                    Ok(s) => s,
+                    Err(e) => {
+                        eprintln!(
+                            "Failed to connect to TCP source {} (attempt {}): {}",
+                            addr, attempt + 1, e
+                        );
+                        if !retry(&mut attempt, max_retries, retry_interval, &label) {
+                            return;
+                        }
+                        continue;
+                    }
+                };
 
-            for line in reader.lines() {
-                if let Ok(data) = line {
-                    let _ = tx.send((port_name.clone(), data));
+                let _ = stream.set_read_timeout(Some(Duration::from_secs(1)));
+                let mut frames = FrameReader::new(stream, Framing::Line);
+                attempt = 0;
+
+                loop {
+                    match frames.next_record() {
+                        Ok(Some(data)) => {
+                            let _ = tx.send((label.clone(), data));
+                        }
+                        Ok(None) => break,
+                        // A `TcpStream` read timeout surfaces as `WouldBlock` on this
+                        // platform rather than `TimedOut` (unlike the serialport crate,
+                        // which always reports `TimedOut`); treat both as "try again".
+                        Err(ref e)
+                            if matches!(
+                                e.kind(),
+                                std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock
+                            ) =>
+                        {
+                            continue
+                        }
+                        Err(_) => break,
+                    }
+                }
+
+                eprintln!("TCP source {} disconnected, will attempt to reconnect", addr);
+                let _ = tx.send((label.clone(), RECONNECT_MARKER.to_string()));
+                if !retry(&mut attempt, max_retries, retry_interval, &label) {
+                    return;
                 }
             }
         });
@@ -154,8 +711,185 @@ fn main() -> std::io::Result<()> {
     drop(tx); // close original sender
 
     for (port_name, line) in rx {
-        writeln!(file, "{},{},{}", timestamp(), port_name, line)?;
+        let record = format!(
+            "{},{},{},{}\n",
+            args.time_resolution.unix_now(),
+            args.time_resolution.elapsed_since(start),
+            port_name,
+            line
+        );
+
+        if current_file.should_rotate(args.rotate_secs, args.rotate_bytes) {
+            let closed_path = current_file.finalize()?;
+            current_file = RecordingFile::open(directory, &staging_dir, args.time_resolution)?;
+            println!("Rotated {:?}, now recording to {:?}", closed_path, current_file.staging_path);
+        }
+
+        current_file.write_record(&record)?;
+        broadcast(&subscribers, &record);
     }
 
+    current_file.finalize()?;
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    #[test]
+    fn time_resolution_parse_rejects_unknown_units() {
+        assert!(TimeResolution::parse("ms").is_ok());
+        assert!(TimeResolution::parse("furlongs").is_err());
+    }
+
+    #[test]
+    fn framing_parse_defaults_and_overrides() {
+        assert!(matches!(Framing::parse("line", 8), Framing::Line));
+        assert!(matches!(Framing::parse("bogus", 8), Framing::Line));
+        assert!(matches!(Framing::parse("length-prefixed", 8), Framing::LengthPrefixed));
+        assert!(matches!(Framing::parse("fixed", 8), Framing::Fixed(8)));
+        assert!(matches!(Framing::parse("fixed:16", 8), Framing::Fixed(16)));
+    }
+
+    /// A `Read` impl that replays a scripted sequence of chunks/errors, used to
+    /// simulate a serial port whose reads occasionally time out mid-frame.
+    struct ScriptedReader {
+        steps: VecDeque<Result<Vec<u8>, std::io::ErrorKind>>,
+    }
+
+    impl Read for ScriptedReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            match self.steps.pop_front() {
+                None => Ok(0),
+                Some(Ok(chunk)) => {
+                    let n = chunk.len().min(buf.len());
+                    buf[..n].copy_from_slice(&chunk[..n]);
+                    Ok(n)
+                }
+                Some(Err(kind)) => Err(std::io::Error::new(kind, "scripted timeout")),
+            }
+        }
+    }
+
+    #[test]
+    fn frame_reader_line_keeps_partial_progress_across_a_timeout() {
+        let reader = ScriptedReader {
+            steps: VecDeque::from([
+                Ok(b"hel".to_vec()),
+                Err(std::io::ErrorKind::TimedOut),
+                Ok(b"lo\nworld\n".to_vec()),
+            ]),
+        };
+        let mut frames = FrameReader::new(reader, Framing::Line);
+
+        let err = frames.next_record().unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+
+        // The "hel" read before the timeout must not have been discarded.
+        assert_eq!(frames.next_record().unwrap().as_deref(), Some("hello"));
+        assert_eq!(frames.next_record().unwrap().as_deref(), Some("world"));
+    }
+
+    #[test]
+    fn frame_reader_line_flushes_a_final_unterminated_line_at_eof() {
+        let reader = ScriptedReader {
+            steps: VecDeque::from([Ok(b"no-newline-before-close".to_vec())]),
+        };
+        let mut frames = FrameReader::new(reader, Framing::Line);
+
+        assert_eq!(
+            frames.next_record().unwrap().as_deref(),
+            Some("no-newline-before-close")
+        );
+        assert_eq!(frames.next_record().unwrap(), None);
+    }
+
+    #[test]
+    fn frame_reader_line_rejects_invalid_utf8() {
+        let reader = ScriptedReader {
+            steps: VecDeque::from([Ok(vec![0xff, 0xfe, b'\n'])]),
+        };
+        let mut frames = FrameReader::new(reader, Framing::Line);
+
+        let err = frames.next_record().unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn tcp_source_preserves_partial_line_across_a_read_timeout() {
+        // Mirrors the --tcp source thread: a short `set_read_timeout` on a real
+        // socket, drained through `FrameReader` the same way the serial path is.
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let sender = thread::spawn(move || {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            stream.write_all(b"hel").unwrap();
+            thread::sleep(Duration::from_millis(150));
+            stream.write_all(b"lo\nworld\n").unwrap();
+        });
+
+        let (server_stream, _) = listener.accept().unwrap();
+        server_stream
+            .set_read_timeout(Some(Duration::from_millis(50)))
+            .unwrap();
+        let mut frames = FrameReader::new(server_stream, Framing::Line);
+
+        let first = loop {
+            match frames.next_record() {
+                Ok(Some(line)) => break line,
+                Ok(None) => panic!("unexpected EOF before any data arrived"),
+                Err(e) if matches!(e.kind(), std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock) => {
+                    continue
+                }
+                Err(e) => panic!("unexpected error: {e}"),
+            }
+        };
+
+        // The "hel" written before the timeout must not have been discarded.
+        assert_eq!(first, "hello");
+        assert_eq!(frames.next_record().unwrap().as_deref(), Some("world"));
+
+        sender.join().unwrap();
+    }
+
+    #[test]
+    fn frame_reader_length_prefixed_rejects_oversized_length() {
+        let oversized = (MAX_FRAME_LEN as u32 + 1).to_be_bytes().to_vec();
+        let reader = ScriptedReader {
+            steps: VecDeque::from([Ok(oversized)]),
+        };
+        let mut frames = FrameReader::new(reader, Framing::LengthPrefixed);
+
+        let err = frames.next_record().unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn recording_file_rotates_within_the_same_second() {
+        let dir = std::env::temp_dir().join(format!(
+            "rat-test-{}-{:?}",
+            std::process::id(),
+            thread::current().id()
+        ));
+        create_dir_all(&dir).unwrap();
+
+        let mut first = RecordingFile::open(&dir, &dir, TimeResolution::Millis).unwrap();
+        let second = RecordingFile::open(&dir, &dir, TimeResolution::Millis).unwrap();
+
+        // Two files opened back-to-back can land in the same wall-clock second; they
+        // must still get distinct names or the later one's rename clobbers the earlier.
+        assert_ne!(first.final_path, second.final_path);
+
+        first.write_record("a,b,c,d\n").unwrap();
+        assert!(first.should_rotate(Some(0), None));
+        assert!(!first.should_rotate(None, Some(u64::MAX)));
+
+        first.finalize().unwrap();
+        second.finalize().unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}